@@ -2,13 +2,16 @@ use anyhow::{Context, Result};
 use gtk4::{gio, glib};
 use gtk4::prelude::*;
 use gtk4::prelude::{ApplicationExt, ApplicationExtManual, DialogExt, FileExt, ListBoxRowExt, ListModelExt, Cast};
-use gtk4::{Application, ApplicationWindow, Button, FileChooserAction, FileChooserDialog, FileFilter, Orientation, Box as GtkBox, Label, ListBox, ListBoxRow, ProgressBar};
+use gtk4::{Application, ApplicationWindow, Button, FileChooserAction, FileChooserDialog, FileFilter, Orientation, Box as GtkBox, Label, ListBox, ListBoxRow, ProgressBar, Picture, Scale};
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 use std::rc::Rc;
 use std::cell::RefCell;
 use percent_encoding::percent_decode_str;
+use notify::{RecursiveMode, Watcher};
+use serde::Deserialize;
 
 
 #[derive(Clone, Default, Debug)]
@@ -16,7 +19,10 @@ struct AppState {
     files: Vec<PathBuf>,
     output_dir: Option<PathBuf>,
     profile: String, // dnxhr_* perfil
-    container: String, // mov | mxf
+    container: String, // mov | mxf | fmp4 | mp4_faststart
+    frag_duration_ms: u32, // fragment duration for the fmp4 container
+    output_mode: String, // file | hls | concat
+    hls_segment_secs: f64,
     audio_bits: u32,   // 16 | 24
     audio_channels: u32, // 2 | 4 | 8
     preserve_fps: bool,
@@ -24,6 +30,92 @@ struct AppState {
     set_timecode: bool,
     timecode: String, // HH:MM:SS:FF
     normalize_ebu_r128: bool,
+    loudness_i: f64, // integrated target LUFS
+    loudness_tp: f64, // true-peak ceiling dBTP
+    loudness_lra: f64, // loudness range target
+    loudness_target: String, // broadcast | podcast | streaming | custom, see LoudnessTarget
+    denoise_enabled: bool,
+    denoise_model: Option<PathBuf>, // path to an .rnnn RNNoise model
+    live_loudness_meter: bool,
+    max_jobs: u32, // how many run_ffmpeg_with_progress calls may run at once
+    channel_routes: Vec<ChannelRoute>, // custom input-channel -> output-track mapping
+    stem_export: bool, // when true, each channel route becomes its own mono/stereo file
+    intro_clip: Option<PathBuf>, // concat mode: prepended before the queued files
+    outro_clip: Option<PathBuf>, // concat mode: appended after the queued files
+    concat_transition: String, // none | fade | fadeblack
+    concat_transition_secs: f64,
+}
+
+// A single output track assembled from one or more input audio channels, e.g.
+// input ch0+ch1 routed to a stereo pair, or input ch2 alone routed to a mono stem.
+#[derive(Clone, Debug)]
+struct ChannelRoute {
+    input_channels: Vec<u32>,
+    label: String,
+}
+
+// Parses the channel-map entry's text format: groups separated by `;`, each
+// group a comma list of input channel indices with an optional `:label`,
+// e.g. "0,1:stereo;2:mic2;3:mic3". Unlabeled groups are named group0, group1, ...
+fn parse_channel_routes(spec: &str) -> Vec<ChannelRoute> {
+    spec.split(';')
+    .map(|s| s.trim())
+    .filter(|s| !s.is_empty())
+    .enumerate()
+    .filter_map(|(i, group)| {
+        let (channels_part, label) = match group.split_once(':') {
+            Some((c, l)) => (c, l.trim().to_string()),
+            None => (group, format!("group{}", i)),
+        };
+        let input_channels: Vec<u32> = channels_part
+        .split(',')
+        .filter_map(|c| c.trim().parse::<u32>().ok())
+        .collect();
+        if input_channels.is_empty() { None } else { Some(ChannelRoute { input_channels, label }) }
+    })
+    .collect()
+}
+
+// Integrated-loudness presets for common delivery destinations. `Custom`
+// means the user dialed in I/TP/LRA by hand via the spin buttons rather than
+// picking a named target.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum LoudnessTarget {
+    Broadcast,               // -23 LUFS, EBU R128 (default)
+    PodcastApple,             // -16 LUFS, Apple Podcasts and most podcast platforms
+    StreamingYoutubeSpotify,  // -14 LUFS, YouTube / Spotify
+    Custom,
+}
+
+impl LoudnessTarget {
+    fn from_id(id: &str) -> Self {
+        match id {
+            "podcast" => LoudnessTarget::PodcastApple,
+            "streaming" => LoudnessTarget::StreamingYoutubeSpotify,
+            "custom" => LoudnessTarget::Custom,
+            _ => LoudnessTarget::Broadcast,
+        }
+    }
+
+    fn id(&self) -> &'static str {
+        match self {
+            LoudnessTarget::Broadcast => "broadcast",
+            LoudnessTarget::PodcastApple => "podcast",
+            LoudnessTarget::StreamingYoutubeSpotify => "streaming",
+            LoudnessTarget::Custom => "custom",
+        }
+    }
+
+    // Integrated target (LUFS), true-peak ceiling (dBTP), loudness range target.
+    // `None` for `Custom`, since those numbers come from the spin buttons instead.
+    fn preset_params(&self) -> Option<(f64, f64, f64)> {
+        match self {
+            LoudnessTarget::Broadcast => Some((-23.0, -1.0, 7.0)),
+            LoudnessTarget::PodcastApple => Some((-16.0, -1.0, 11.0)),
+            LoudnessTarget::StreamingYoutubeSpotify => Some((-14.0, -1.0, 11.0)),
+            LoudnessTarget::Custom => None,
+        }
+    }
 }
 
 fn main() -> Result<()> {
@@ -107,19 +199,70 @@ fn build_ui(app: &Application) -> Result<()> {
     // Output container
     let combo_container = gtk4::ComboBoxText::new();
     for c in ["mov", "mxf"] { combo_container.append(Some(c), c); }
+    combo_container.append(Some("fmp4"), "Fragmented MP4 (CMAF)");
+    combo_container.append(Some("mp4_faststart"), "Streaming MP4 (faststart)");
     combo_container.set_active_id(Some("mov"));
 
+    // Fragment duration, used only when the fmp4 container is selected
+    let lbl_frag_duration = Label::new(Some("Frag (ms):"));
+    let spin_frag_duration = gtk4::SpinButton::with_range(100.0, 60000.0, 100.0);
+    spin_frag_duration.set_value(2000.0);
+    spin_frag_duration.set_sensitive(false);
+
+    // Output mode: single file (mov/mxf/fmp4), an HLS segmented presentation,
+    // or one batch-concatenated master
+    let combo_output_mode = gtk4::ComboBoxText::new();
+    combo_output_mode.append(Some("file"), "Single file");
+    combo_output_mode.append(Some("hls"), "HLS segments");
+    combo_output_mode.append(Some("concat"), "Batch concat (intro/outro + transitions)");
+    combo_output_mode.set_active_id(Some("file"));
+
+    let lbl_hls_segment = Label::new(Some("HLS seg (s):"));
+    let spin_hls_segment = gtk4::SpinButton::with_range(1.0, 30.0, 1.0);
+    spin_hls_segment.set_value(6.0);
+    spin_hls_segment.set_sensitive(false);
+
+    // Batch concat mode: stitches the queued files (plus an optional intro
+    // and outro) into a single deliverable with a hard cut or a transition
+    // at each clip boundary.
+    let btn_intro_clip = Button::with_label("Intro clip...");
+    btn_intro_clip.set_sensitive(false);
+    let lbl_intro_clip = Label::new(Some("(no intro)"));
+    lbl_intro_clip.set_xalign(0.0);
+    let btn_outro_clip = Button::with_label("Outro clip...");
+    btn_outro_clip.set_sensitive(false);
+    let lbl_outro_clip = Label::new(Some("(no outro)"));
+    lbl_outro_clip.set_xalign(0.0);
+    let combo_concat_transition = gtk4::ComboBoxText::new();
+    combo_concat_transition.append(Some("none"), "Hard cut");
+    combo_concat_transition.append(Some("fade"), "Crossfade");
+    combo_concat_transition.append(Some("fadeblack"), "Fade through black");
+    combo_concat_transition.set_active_id(Some("none"));
+    combo_concat_transition.set_sensitive(false);
+    let lbl_concat_transition_secs = Label::new(Some("Transition (s):"));
+    let spin_concat_transition_secs = gtk4::SpinButton::with_range(0.1, 10.0, 0.1);
+    spin_concat_transition_secs.set_value(1.0);
+    spin_concat_transition_secs.set_sensitive(false);
+
     // Audio Depth
     let combo_audio = gtk4::ComboBoxText::new();
     combo_audio.append(Some("16"), "PCM 16-bit");
     combo_audio.append(Some("24"), "PCM 24-bit");
     combo_audio.set_active_id(Some("16"));
 
-    // Audio Channels
+    // Audio Channels (plain interleave, used when no custom channel map is given)
     let combo_channels = gtk4::ComboBoxText::new();
     for ch in [2,4,8] { combo_channels.append(Some(&ch.to_string()), &format!("{} ch", ch)); }
     combo_channels.set_active_id(Some("2"));
 
+    // Per-channel audio mapping: route input channels to one or more output
+    // tracks, e.g. "0,1:stereo;2:mic2;3:mic3". Overrides combo_channels for
+    // single-file output when non-empty; HLS and batch concat output ignore it.
+    let entry_channel_map = gtk4::Entry::new();
+    entry_channel_map.set_placeholder_text(Some("Channel map e.g. 0,1:stereo;2:mic2"));
+    entry_channel_map.set_width_chars(24);
+    let chk_stem_export = gtk4::CheckButton::with_label("Export stems as separate files");
+
     // Timecode
     let chk_timecode = gtk4::CheckButton::with_label("Define timecode");
     let entry_timecode = gtk4::Entry::new();
@@ -127,8 +270,42 @@ fn build_ui(app: &Application) -> Result<()> {
     entry_timecode.set_width_chars(10);
     entry_timecode.set_sensitive(false);
 
-    // Normalization EBU R128
-    let chk_normalize = gtk4::CheckButton::with_label("Normalize audio (EBU R128 -23 LUFS)");
+    // Normalization EBU R128: two-pass loudnorm with configurable targets
+    let chk_normalize = gtk4::CheckButton::with_label("Normalize audio (EBU R128)");
+    let combo_loudness_target = gtk4::ComboBoxText::new();
+    combo_loudness_target.append(Some("broadcast"), "Broadcast (-23 LUFS)");
+    combo_loudness_target.append(Some("podcast"), "Podcast / Apple (-16 LUFS)");
+    combo_loudness_target.append(Some("streaming"), "Streaming: YouTube / Spotify (-14 LUFS)");
+    combo_loudness_target.append(Some("custom"), "Custom");
+    combo_loudness_target.set_active_id(Some("broadcast"));
+    combo_loudness_target.set_sensitive(false);
+    let lbl_loud_i = Label::new(Some("I (LUFS):"));
+    let spin_loud_i = gtk4::SpinButton::with_range(-70.0, -5.0, 0.5);
+    spin_loud_i.set_value(-23.0);
+    spin_loud_i.set_sensitive(false);
+    let lbl_loud_tp = Label::new(Some("TP (dBTP):"));
+    let spin_loud_tp = gtk4::SpinButton::with_range(-9.0, 0.0, 0.1);
+    spin_loud_tp.set_value(-1.0);
+    spin_loud_tp.set_sensitive(false);
+    let lbl_loud_lra = Label::new(Some("LRA:"));
+    let spin_loud_lra = gtk4::SpinButton::with_range(1.0, 20.0, 0.5);
+    spin_loud_lra.set_value(7.0);
+    spin_loud_lra.set_sensitive(false);
+
+    // Live EBU R128 loudness meter surfaced during the actual encode
+    let chk_live_meter = gtk4::CheckButton::with_label("Live loudness meter");
+
+    // Optional RNNoise speech denoise pass, run before any loudness normalization
+    let chk_denoise = gtk4::CheckButton::with_label("Denoise (RNNoise)");
+    let btn_denoise_model = Button::with_label("Model (.rnnn)...");
+    btn_denoise_model.set_sensitive(false);
+    let lbl_denoise_model = Label::new(Some("(no model selected)"));
+    lbl_denoise_model.set_xalign(0.0);
+
+    // Concurrency: how many clips are transcoded at once
+    let lbl_max_jobs = Label::new(Some("Parallel jobs:"));
+    let spin_max_jobs = gtk4::SpinButton::with_range(1.0, 16.0, 1.0);
+    spin_max_jobs.set_value(2.0);
 
     // Preserving FPS + FPS target
     let chk_preserve_fps = gtk4::CheckButton::with_label("Preserve FPS");
@@ -152,13 +329,40 @@ fn build_ui(app: &Application) -> Result<()> {
     controls.append(&btn_select_output);
     controls.append(&combo_profile);
     controls.append(&combo_container);
+    controls.append(&lbl_frag_duration);
+    controls.append(&spin_frag_duration);
+    controls.append(&combo_output_mode);
+    controls.append(&lbl_hls_segment);
+    controls.append(&spin_hls_segment);
+    controls.append(&btn_intro_clip);
+    controls.append(&lbl_intro_clip);
+    controls.append(&btn_outro_clip);
+    controls.append(&lbl_outro_clip);
+    controls.append(&combo_concat_transition);
+    controls.append(&lbl_concat_transition_secs);
+    controls.append(&spin_concat_transition_secs);
     controls.append(&combo_audio);
     controls.append(&combo_channels);
+    controls.append(&entry_channel_map);
+    controls.append(&chk_stem_export);
     controls.append(&chk_preserve_fps);
     controls.append(&spin_fps);
     controls.append(&chk_timecode);
     controls.append(&entry_timecode);
     controls.append(&chk_normalize);
+    controls.append(&combo_loudness_target);
+    controls.append(&lbl_loud_i);
+    controls.append(&spin_loud_i);
+    controls.append(&lbl_loud_tp);
+    controls.append(&spin_loud_tp);
+    controls.append(&lbl_loud_lra);
+    controls.append(&spin_loud_lra);
+    controls.append(&chk_live_meter);
+    controls.append(&chk_denoise);
+    controls.append(&btn_denoise_model);
+    controls.append(&lbl_denoise_model);
+    controls.append(&lbl_max_jobs);
+    controls.append(&spin_max_jobs);
     controls.append(&btn_start);
 
     // Output information
@@ -168,9 +372,21 @@ fn build_ui(app: &Application) -> Result<()> {
     // Jobs List
     let list = ListBox::new();
 
+    // Preview pane: thumbnail for the selected row, scrubbable via a seek slider
+    let preview_box = GtkBox::new(Orientation::Vertical, 4);
+    let picture_preview = Picture::new();
+    picture_preview.set_size_request(320, 180);
+    picture_preview.set_halign(gtk4::Align::Center);
+    let scale_seek = Scale::with_range(Orientation::Horizontal, 0.0, 1.0, 0.1);
+    scale_seek.set_hexpand(true);
+    scale_seek.set_sensitive(false);
+    preview_box.append(&picture_preview);
+    preview_box.append(&scale_seek);
+
     root.append(&controls);
     root.append(&lbl_output);
     root.append(&list);
+    root.append(&preview_box);
 
     let vbox = GtkBox::new(Orientation::Vertical, 1);
 
@@ -190,6 +406,11 @@ fn build_ui(app: &Application) -> Result<()> {
     let mut st = state.lock().unwrap();
     st.profile = "dnxhr_hq".to_string();
     st.container = "mov".to_string();
+    st.frag_duration_ms = 2000;
+    st.output_mode = "file".to_string();
+    st.hls_segment_secs = 6.0;
+    st.channel_routes = Vec::new();
+    st.stem_export = false;
     st.audio_bits = 16;
     st.audio_channels = 2;
     st.preserve_fps = true;
@@ -197,6 +418,18 @@ fn build_ui(app: &Application) -> Result<()> {
     st.set_timecode = false;
     st.timecode = "00:00:00:00".to_string();
     st.normalize_ebu_r128 = false;
+    st.loudness_i = -23.0;
+    st.loudness_tp = -1.0;
+    st.loudness_lra = 7.0;
+    st.loudness_target = "broadcast".to_string();
+    st.denoise_enabled = false;
+    st.denoise_model = None;
+    st.live_loudness_meter = false;
+    st.max_jobs = 2;
+    st.intro_clip = None;
+    st.outro_clip = None;
+    st.concat_transition = "none".to_string();
+    st.concat_transition_secs = 1.0;
 }
 
     // Handler: change system Dark/Light Style
@@ -220,13 +453,136 @@ fn build_ui(app: &Application) -> Result<()> {
     // Handlers: container / audio / fps
     {
         let state = Arc::clone(&state);
+        let spin_frag_duration = spin_frag_duration.clone();
         combo_container.connect_changed(move |c| {
             if let Some(id) = c.active_id() {
+                spin_frag_duration.set_sensitive(id == "fmp4");
                 let mut st = state.lock().unwrap();
                 st.container = id.to_string();
             }
         });
     }
+    {
+        let state = Arc::clone(&state);
+        spin_frag_duration.connect_value_changed(move |s| {
+            let mut st = state.lock().unwrap();
+            st.frag_duration_ms = s.value() as u32;
+        });
+    }
+    {
+        let state = Arc::clone(&state);
+        let spin_hls_segment = spin_hls_segment.clone();
+        let btn_intro_clip = btn_intro_clip.clone();
+        let btn_outro_clip = btn_outro_clip.clone();
+        let combo_concat_transition = combo_concat_transition.clone();
+        let spin_concat_transition_secs = spin_concat_transition_secs.clone();
+        let chk_normalize = chk_normalize.clone();
+        let chk_denoise = chk_denoise.clone();
+        let chk_timecode = chk_timecode.clone();
+        combo_output_mode.connect_changed(move |c| {
+            if let Some(id) = c.active_id() {
+                spin_hls_segment.set_sensitive(id == "hls");
+                let is_concat = id == "concat";
+                btn_intro_clip.set_sensitive(is_concat);
+                btn_outro_clip.set_sensitive(is_concat);
+                combo_concat_transition.set_sensitive(is_concat);
+                spin_concat_transition_secs.set_sensitive(is_concat);
+                // Batch concat doesn't run normalize/denoise/timecode through its
+                // filter graph, so gray those controls out rather than let them
+                // imply a processing step the rendered master never applies.
+                chk_normalize.set_sensitive(!is_concat);
+                chk_denoise.set_sensitive(!is_concat);
+                chk_timecode.set_sensitive(!is_concat);
+                let concat_tooltip = if is_concat { Some("Not available in batch concat mode") } else { None };
+                chk_normalize.set_tooltip_text(concat_tooltip);
+                chk_denoise.set_tooltip_text(concat_tooltip);
+                chk_timecode.set_tooltip_text(concat_tooltip);
+                let mut st = state.lock().unwrap();
+                st.output_mode = id.to_string();
+            }
+        });
+    }
+    {
+        let state = Arc::clone(&state);
+        spin_hls_segment.connect_value_changed(move |s| {
+            let mut st = state.lock().unwrap();
+            st.hls_segment_secs = s.value();
+        });
+    }
+    {
+        let window = window.clone();
+        let state = Arc::clone(&state);
+        let lbl_intro_clip = lbl_intro_clip.clone();
+        btn_intro_clip.connect_clicked(move |_| {
+            let dlg = FileChooserDialog::new(
+                Some("Select intro clip"),
+                Some(&window),
+                FileChooserAction::Open,
+                &[("Cancel", gtk4::ResponseType::Cancel), ("Select", gtk4::ResponseType::Accept)],
+                );
+            dlg.set_modal(true);
+            dlg.connect_response({
+                let state = Arc::clone(&state);
+                let lbl_intro_clip = lbl_intro_clip.clone();
+                move |dlg, resp| {
+                    if resp == gtk4::ResponseType::Accept {
+                        if let Some(path) = dlg.file().and_then(|f| f.path()) {
+                            lbl_intro_clip.set_text(&path.display().to_string());
+                            let mut st = state.lock().unwrap();
+                            st.intro_clip = Some(path);
+                        }
+                    }
+                    dlg.close();
+                }
+            });
+            dlg.show();
+        });
+    }
+    {
+        let window = window.clone();
+        let state = Arc::clone(&state);
+        let lbl_outro_clip = lbl_outro_clip.clone();
+        btn_outro_clip.connect_clicked(move |_| {
+            let dlg = FileChooserDialog::new(
+                Some("Select outro clip"),
+                Some(&window),
+                FileChooserAction::Open,
+                &[("Cancel", gtk4::ResponseType::Cancel), ("Select", gtk4::ResponseType::Accept)],
+                );
+            dlg.set_modal(true);
+            dlg.connect_response({
+                let state = Arc::clone(&state);
+                let lbl_outro_clip = lbl_outro_clip.clone();
+                move |dlg, resp| {
+                    if resp == gtk4::ResponseType::Accept {
+                        if let Some(path) = dlg.file().and_then(|f| f.path()) {
+                            lbl_outro_clip.set_text(&path.display().to_string());
+                            let mut st = state.lock().unwrap();
+                            st.outro_clip = Some(path);
+                        }
+                    }
+                    dlg.close();
+                }
+            });
+            dlg.show();
+        });
+    }
+    {
+        let state = Arc::clone(&state);
+        combo_concat_transition.connect_changed(move |c| {
+            if let Some(id) = c.active_id() {
+                let mut st = state.lock().unwrap();
+                st.concat_transition = id.to_string();
+            }
+        });
+    }
+    {
+        let state = Arc::clone(&state);
+        spin_concat_transition_secs.connect_value_changed(move |s| {
+            let mut st = state.lock().unwrap();
+            st.concat_transition_secs = s.value();
+        });
+    }
     {
         let state = Arc::clone(&state);
         combo_audio.connect_changed(move |c| {
@@ -245,6 +601,44 @@ fn build_ui(app: &Application) -> Result<()> {
             }
         });
     }
+    {
+        let state = Arc::clone(&state);
+        let chk_normalize = chk_normalize.clone();
+        let chk_denoise = chk_denoise.clone();
+        let chk_live_meter = chk_live_meter.clone();
+        entry_channel_map.connect_changed(move |e| {
+            let routes = parse_channel_routes(&e.text());
+            let has_routes = !routes.is_empty();
+            {
+                let mut st = state.lock().unwrap();
+                st.channel_routes = routes;
+            }
+            // A custom channel map occupies the filter graph with `pan`, and
+            // normalize/denoise/the live meter all build their own -af chain on
+            // top of it; rather than silently drop them (ffmpeg still runs but
+            // un-normalized/un-denoised), grey them out and uncheck them the
+            // same way batch concat mode does for its own unsupported toggles.
+            if has_routes {
+                chk_normalize.set_active(false);
+                chk_denoise.set_active(false);
+                chk_live_meter.set_active(false);
+            }
+            let tooltip = if has_routes { Some("Not available with a custom channel map") } else { None };
+            chk_normalize.set_sensitive(!has_routes);
+            chk_normalize.set_tooltip_text(tooltip);
+            chk_denoise.set_sensitive(!has_routes);
+            chk_denoise.set_tooltip_text(tooltip);
+            chk_live_meter.set_sensitive(!has_routes);
+            chk_live_meter.set_tooltip_text(tooltip);
+        });
+    }
+    {
+        let state = Arc::clone(&state);
+        chk_stem_export.connect_toggled(move |chk| {
+            let mut st = state.lock().unwrap();
+            st.stem_export = chk.is_active();
+        });
+    }
     {
         let state = Arc::clone(&state);
         let spin = spin_fps.clone();
@@ -274,9 +668,114 @@ fn build_ui(app: &Application) -> Result<()> {
     }
     {
         let state = Arc::clone(&state);
+        let spin_loud_i = spin_loud_i.clone();
+        let spin_loud_tp = spin_loud_tp.clone();
+        let spin_loud_lra = spin_loud_lra.clone();
+        let combo_loudness_target = combo_loudness_target.clone();
         chk_normalize.connect_toggled(move |chk| {
+            let active = chk.is_active();
+            let is_custom = combo_loudness_target.active_id().as_deref() == Some("custom");
+            combo_loudness_target.set_sensitive(active);
+            spin_loud_i.set_sensitive(active && is_custom);
+            spin_loud_tp.set_sensitive(active && is_custom);
+            spin_loud_lra.set_sensitive(active && is_custom);
+            let mut st = state.lock().unwrap();
+            st.normalize_ebu_r128 = active;
+        });
+    }
+    {
+        let state = Arc::clone(&state);
+        let chk_normalize = chk_normalize.clone();
+        let spin_loud_i = spin_loud_i.clone();
+        let spin_loud_tp = spin_loud_tp.clone();
+        let spin_loud_lra = spin_loud_lra.clone();
+        combo_loudness_target.connect_changed(move |combo| {
+            let id = combo.active_id().map(|s| s.to_string()).unwrap_or_else(|| "broadcast".to_string());
+            let target = LoudnessTarget::from_id(&id);
+            let is_custom = target == LoudnessTarget::Custom;
+            spin_loud_i.set_sensitive(chk_normalize.is_active() && is_custom);
+            spin_loud_tp.set_sensitive(chk_normalize.is_active() && is_custom);
+            spin_loud_lra.set_sensitive(chk_normalize.is_active() && is_custom);
+            if let Some((i, tp, lra)) = target.preset_params() {
+                spin_loud_i.set_value(i);
+                spin_loud_tp.set_value(tp);
+                spin_loud_lra.set_value(lra);
+            }
+            let mut st = state.lock().unwrap();
+            st.loudness_target = id;
+        });
+    }
+    {
+        let state = Arc::clone(&state);
+        spin_loud_i.connect_value_changed(move |s| {
+            let mut st = state.lock().unwrap();
+            st.loudness_i = s.value();
+        });
+    }
+    {
+        let state = Arc::clone(&state);
+        spin_loud_tp.connect_value_changed(move |s| {
             let mut st = state.lock().unwrap();
-            st.normalize_ebu_r128 = chk.is_active();
+            st.loudness_tp = s.value();
+        });
+    }
+    {
+        let state = Arc::clone(&state);
+        spin_loud_lra.connect_value_changed(move |s| {
+            let mut st = state.lock().unwrap();
+            st.loudness_lra = s.value();
+        });
+    }
+    {
+        let state = Arc::clone(&state);
+        chk_live_meter.connect_toggled(move |chk| {
+            let mut st = state.lock().unwrap();
+            st.live_loudness_meter = chk.is_active();
+        });
+    }
+    {
+        let state = Arc::clone(&state);
+        let btn_denoise_model = btn_denoise_model.clone();
+        chk_denoise.connect_toggled(move |chk| {
+            let active = chk.is_active();
+            btn_denoise_model.set_sensitive(active);
+            let mut st = state.lock().unwrap();
+            st.denoise_enabled = active;
+        });
+    }
+    {
+        let window = window.clone();
+        let state = Arc::clone(&state);
+        let lbl_denoise_model = lbl_denoise_model.clone();
+        btn_denoise_model.connect_clicked(move |_| {
+            let dlg = FileChooserDialog::new(
+                Some("Select RNNoise model"),
+                Some(&window),
+                FileChooserAction::Open,
+                &[("Cancel", gtk4::ResponseType::Cancel), ("Select", gtk4::ResponseType::Accept)],
+                );
+            dlg.set_modal(true);
+
+            let filter_rnnn = FileFilter::new();
+            filter_rnnn.add_pattern("*.rnnn");
+            filter_rnnn.set_name(Some("RNNoise models (*.rnnn)"));
+            dlg.add_filter(&filter_rnnn);
+
+            dlg.connect_response({
+                let state = Arc::clone(&state);
+                let lbl_denoise_model = lbl_denoise_model.clone();
+                move |dlg, resp| {
+                    if resp == gtk4::ResponseType::Accept {
+                        if let Some(path) = dlg.file().and_then(|f| f.path()) {
+                            lbl_denoise_model.set_text(&path.display().to_string());
+                            let mut st = state.lock().unwrap();
+                            st.denoise_model = Some(path);
+                        }
+                    }
+                    dlg.close();
+                }
+            });
+            dlg.show();
         });
     }
     {
@@ -286,6 +785,13 @@ fn build_ui(app: &Application) -> Result<()> {
             st.target_fps = s.value();
         });
     }
+    {
+        let state = Arc::clone(&state);
+        spin_max_jobs.connect_value_changed(move |s| {
+            let mut st = state.lock().unwrap();
+            st.max_jobs = s.value() as u32;
+        });
+    }
 
     // Handler: select files
     {
@@ -337,6 +843,7 @@ fn build_ui(app: &Application) -> Result<()> {
                             // Fill in
                             for p in paths {
                                 let row = ListBoxRow::new();
+                                row.set_data("file-path", p.clone());
                                 let hb = GtkBox::new(Orientation::Horizontal, 8);
                                 let label = Label::new(Some(p.file_name().and_then(|s| s.to_str()).unwrap_or("(no name)")));
                                 label.set_xalign(0.0);
@@ -428,6 +935,7 @@ fn build_ui(app: &Application) -> Result<()> {
                     }
                     for p in added {
                         let row = ListBoxRow::new();
+                        row.set_data("file-path", p.clone());
                         let hb = GtkBox::new(Orientation::Horizontal, 8);
                         let label = Label::new(Some(p.file_name().and_then(|s| s.to_str()).unwrap_or("(no name)")));
                         label.set_xalign(0.0);
@@ -447,6 +955,56 @@ fn build_ui(app: &Application) -> Result<()> {
         });
         window.add_controller(drop);
     }
+    // Handler: preview pane (thumbnail + scrubbing) for the selected row
+    let selected_preview_path: Rc<RefCell<Option<PathBuf>>> = Rc::new(RefCell::new(None));
+    let (tx_thumb, rx_thumb) = glib::MainContext::channel::<PathBuf>(0.into());
+    {
+        let picture_preview = picture_preview.clone();
+        rx_thumb.attach(None, move |png_path| {
+            picture_preview.set_filename(Some(&png_path));
+            glib::ControlFlow::Continue
+        });
+    }
+    // Dragging the seek slider fires connect_value_changed repeatedly, so each
+    // request bumps this generation counter; a request only delivers its
+    // thumbnail (and otherwise cleans up after itself) if it's still the most
+    // recent one by the time ffmpeg finishes.
+    let thumbnail_generation = Arc::new(AtomicU64::new(0));
+    {
+        let selected_preview_path = Rc::clone(&selected_preview_path);
+        let scale_seek = scale_seek.clone();
+        let tx_thumb = tx_thumb.clone();
+        let thumbnail_generation = Arc::clone(&thumbnail_generation);
+        list.connect_row_selected(move |_, row| {
+            let row = match row {
+                Some(r) => r,
+                None => return,
+            };
+            let path = match unsafe { row.data::<PathBuf>("file-path") } {
+                Some(p) => unsafe { p.as_ref().clone() },
+                None => return,
+            };
+            let duration = probe_duration_secs(&path).unwrap_or(0.0).max(0.1);
+            scale_seek.set_range(0.0, duration);
+            scale_seek.set_value(0.0);
+            scale_seek.set_sensitive(true);
+            *selected_preview_path.borrow_mut() = Some(path.clone());
+            let generation = thumbnail_generation.fetch_add(1, Ordering::SeqCst) + 1;
+            spawn_thumbnail(path, 0.0, tx_thumb.clone(), generation, Arc::clone(&thumbnail_generation));
+        });
+    }
+    {
+        let selected_preview_path = Rc::clone(&selected_preview_path);
+        let tx_thumb = tx_thumb.clone();
+        let thumbnail_generation = Arc::clone(&thumbnail_generation);
+        scale_seek.connect_value_changed(move |s| {
+            if let Some(path) = selected_preview_path.borrow().clone() {
+                let generation = thumbnail_generation.fetch_add(1, Ordering::SeqCst) + 1;
+                spawn_thumbnail(path, s.value(), tx_thumb.clone(), generation, Arc::clone(&thumbnail_generation));
+            }
+        });
+    }
+
     // Handler: start conversion
     {
         let list = list.clone();
@@ -495,41 +1053,177 @@ fn build_ui(app: &Application) -> Result<()> {
                 let out_dir = base_out.join("transcoded");
                 let _ = std::fs::create_dir_all(&out_dir);
 
-                for (idx, input) in st.files.iter().enumerate() {
-                    let output_name = input.file_stem().and_then(|s| s.to_str()).unwrap_or("output");
-                    let ext = if st.container == "mxf" { "mxf" } else { "mov" };
-                    let output = out_dir.join(format!("{}.{}", output_name, ext));
-
-                    // Initial progress update
-                    eprintln!("[DEBUG] Starting job {} for {}", idx, input.display());
-                    let _ = tx_thread.send((idx, String::from("Starting..."), 0.01));
-
-                    // Process file with progress updates
+                if st.output_mode == "concat" {
+                    // One combined job rather than the per-file queue below: the
+                    // whole batch becomes a single ffmpeg invocation, so report
+                    // progress against the first row.
+                    let ext = match st.container.as_str() {
+                        "mxf" => "mxf",
+                        "fmp4" | "mp4_faststart" => "mp4",
+                        _ => "mov",
+                    };
+                    let output = out_dir.join(format!("concat_master.{}", ext));
                     let tx_progress = tx_thread.clone();
-                    match run_ffmpeg_with_progress(
-                        input,
+                    let _ = tx_thread.send((0, String::from("Starting concat..."), 0.01));
+                    let result = run_ffmpeg_concat_with_progress(
+                        st.intro_clip.as_deref(),
+                        &st.files,
+                        st.outro_clip.as_deref(),
+                        &st.concat_transition,
+                        st.concat_transition_secs,
                         &output,
                         &st.profile,
+                        &st.container,
+                        st.frag_duration_ms,
                         st.audio_bits,
                         st.audio_channels,
-                        st.preserve_fps,
-                        st.target_fps,
-                        st.set_timecode,
-                        &st.timecode,
-                        st.normalize_ebu_r128,
                         move |frac| {
-                            let _ = tx_progress.send((idx, String::from("Converting..."), frac));
+                            let _ = tx_progress.send((0, String::from("Concatenating..."), frac));
                         },
-                        ) {
-                        Ok(_) => {
-                            eprintln!("[DEBUG] Job {} completed", idx);
-                            let _ = tx_thread.send((idx, String::from("Completed"), 1.0));
-                        }
-                        Err(e) => {
-                            eprintln!("[DEBUG] Job {} error: {}", idx, e);
-                            let _ = tx_thread.send((idx, format!("Error: {}", e), 0.0));
-                        }
+                        );
+                    match result {
+                        Ok(()) => { let _ = tx_thread.send((0, String::from("Done"), 1.0)); }
+                        Err(e) => { let _ = tx_thread.send((0, format!("Error: {}", e), 0.0)); }
                     }
+                    return;
+                }
+
+                // Shared work queue: each slot pops the next (idx, input) and runs it
+                // to completion (an EBU two-pass job still occupies a single slot).
+                let queue: Arc<Mutex<std::collections::VecDeque<(usize, PathBuf)>>> =
+                    Arc::new(Mutex::new(st.files.iter().cloned().enumerate().collect()));
+
+                let worker_count = st.max_jobs.max(1).min(st.files.len() as u32) as usize;
+                eprintln!("[DEBUG] Starting {} worker(s) for {} file(s)", worker_count, st.files.len());
+
+                let mut handles = Vec::with_capacity(worker_count);
+                for _ in 0..worker_count {
+                    let queue = Arc::clone(&queue);
+                    let tx_thread = tx_thread.clone();
+                    let st = st.clone();
+                    let out_dir = out_dir.clone();
+                    handles.push(std::thread::spawn(move || {
+                        loop {
+                            let next = queue.lock().unwrap().pop_front();
+                            let (idx, input) = match next {
+                                Some(v) => v,
+                                None => break,
+                            };
+
+                            let output_name = input.file_stem().and_then(|s| s.to_str()).unwrap_or("output");
+
+                            eprintln!("[DEBUG] Starting job {} for {}", idx, input.display());
+                            let _ = tx_thread.send((idx, String::from("Starting..."), 0.01));
+
+                            let tx_progress = tx_thread.clone();
+                            let result = if st.output_mode == "hls" {
+                                let hls_dir = out_dir.join(output_name);
+                                let tx_warn = tx_progress.clone();
+                                let last_frac = Arc::new(Mutex::new(0.0f64));
+                                let last_frac_progress = Arc::clone(&last_frac);
+                                let tx_loudness = tx_progress.clone();
+                                run_ffmpeg_hls_with_progress(
+                                    &input,
+                                    &hls_dir,
+                                    &st.profile,
+                                    st.audio_bits,
+                                    st.audio_channels,
+                                    st.preserve_fps,
+                                    st.target_fps,
+                                    st.set_timecode,
+                                    &st.timecode,
+                                    st.hls_segment_secs,
+                                    st.normalize_ebu_r128,
+                                    st.loudness_i,
+                                    st.loudness_tp,
+                                    st.loudness_lra,
+                                    LoudnessTarget::from_id(&st.loudness_target),
+                                    if st.denoise_enabled { st.denoise_model.as_deref() } else { None },
+                                    st.live_loudness_meter,
+                                    if st.live_loudness_meter {
+                                        Some(Box::new(move |m: f64, s: f64, i: f64, lra: f64| {
+                                            let frac = *last_frac.lock().unwrap();
+                                            let status = format!("M:{:.1} S:{:.1} I:{:.1} LUFS LRA:{:.1}", m, s, i, lra);
+                                            let _ = tx_loudness.send((idx, status, frac));
+                                        }) as Box<dyn FnMut(f64, f64, f64, f64) + Send>)
+                                    } else {
+                                        None
+                                    },
+                                    move |warning| {
+                                        let _ = tx_warn.send((idx, format!("Warning: {}", warning), 0.02));
+                                    },
+                                    move |frac| {
+                                        *last_frac_progress.lock().unwrap() = frac.max(0.0);
+                                        let _ = tx_progress.send((idx, String::from("Converting..."), frac));
+                                    },
+                                    )
+                            } else {
+                                let ext = match st.container.as_str() {
+                                    "mxf" => "mxf",
+                                    "fmp4" | "mp4_faststart" => "mp4",
+                                    _ => "mov",
+                                };
+                                let output = out_dir.join(format!("{}.{}", output_name, ext));
+                                let tx_warn = tx_progress.clone();
+                                let last_frac = Arc::new(Mutex::new(0.0f64));
+                                let last_frac_progress = Arc::clone(&last_frac);
+                                let tx_loudness = tx_progress.clone();
+                                run_ffmpeg_with_progress(
+                                    &input,
+                                    &output,
+                                    &st.profile,
+                                    &st.container,
+                                    st.frag_duration_ms,
+                                    st.audio_bits,
+                                    st.audio_channels,
+                                    &st.channel_routes,
+                                    st.stem_export,
+                                    st.preserve_fps,
+                                    st.target_fps,
+                                    st.set_timecode,
+                                    &st.timecode,
+                                    st.normalize_ebu_r128,
+                                    st.loudness_i,
+                                    st.loudness_tp,
+                                    st.loudness_lra,
+                                    LoudnessTarget::from_id(&st.loudness_target),
+                                    if st.denoise_enabled { st.denoise_model.as_deref() } else { None },
+                                    st.live_loudness_meter,
+                                    if st.live_loudness_meter {
+                                        Some(Box::new(move |m: f64, s: f64, i: f64, lra: f64| {
+                                            let frac = *last_frac.lock().unwrap();
+                                            let status = format!("M:{:.1} S:{:.1} I:{:.1} LUFS LRA:{:.1}", m, s, i, lra);
+                                            let _ = tx_loudness.send((idx, status, frac));
+                                        }) as Box<dyn FnMut(f64, f64, f64, f64) + Send>)
+                                    } else {
+                                        None
+                                    },
+                                    move |warning| {
+                                        let _ = tx_warn.send((idx, format!("Warning: {}", warning), 0.02));
+                                    },
+                                    move |frac| {
+                                        *last_frac_progress.lock().unwrap() = frac.max(0.0);
+                                        let _ = tx_progress.send((idx, String::from("Converting..."), frac));
+                                    },
+                                    )
+                            };
+
+                            match result {
+                                Ok(_) => {
+                                    eprintln!("[DEBUG] Job {} completed", idx);
+                                    let _ = tx_thread.send((idx, String::from("Completed"), 1.0));
+                                }
+                                Err(e) => {
+                                    eprintln!("[DEBUG] Job {} error: {}", idx, e);
+                                    let _ = tx_thread.send((idx, format!("Error: {}", e), 0.0));
+                                }
+                            }
+                        }
+                    }));
+                }
+
+                for h in handles {
+                    let _ = h.join();
                 }
             });
 
@@ -589,12 +1283,85 @@ fn clear_children(list: &ListBox) {
     }
 }
 
-fn run_ffmpeg_with_progress(input: &Path, output: &Path, profile: &str, audio_bits: u32, audio_channels: u32, preserve_fps: bool, target_fps: f64, set_timecode: bool, timecode: &str, normalize_ebu_r128: bool, mut on_progress: impl FnMut(f64) + Send + 'static) -> Result<()> {
-    // Get duration with ffprobe to calculate fraction
-    let duration = probe_duration_secs(input).unwrap_or(0.0);
+fn run_ffmpeg_with_progress(input: &Path, output: &Path, profile: &str, container: &str, frag_duration_ms: u32, audio_bits: u32, audio_channels: u32, channel_routes: &[ChannelRoute], stem_export: bool, preserve_fps: bool, target_fps: f64, set_timecode: bool, timecode: &str, normalize_ebu_r128: bool, loudness_i: f64, loudness_tp: f64, loudness_lra: f64, loudness_target: LoudnessTarget, denoise: Option<&Path>, live_loudness_meter: bool, mut on_loudness: Option<Box<dyn FnMut(f64, f64, f64, f64) + Send>>, mut on_warning: impl FnMut(String) + Send + 'static, on_progress: impl FnMut(f64) + Send + 'static) -> Result<()> {
+    // Probe once: duration (to compute the progress fraction), source bit
+    // depth (to warn when the profile's 8-bit format downsamples it; the
+    // pixel format itself follows the profile only, see `select_pix_fmt`), and the
+    // source audio sample rate (so PCM output matches it instead of
+    // whatever ffmpeg would otherwise default to).
+    let source_info = probe_source_info(input).unwrap_or_default();
+    let duration = source_info.duration_secs.unwrap_or(0.0);
+    if let Some(warning) = bit_depth_downsample_warning(profile, source_info.bit_depth) {
+        on_warning(warning);
+    }
+    // A custom channel map occupies the filter graph with `pan`, so
+    // normalize/denoise/the live meter (each its own -af chain) can't ride
+    // along; the UI already disables those toggles once a map is entered, but
+    // warn here too rather than depend solely on that to keep the silence honest.
+    if !channel_routes.is_empty() && (normalize_ebu_r128 || denoise.is_some() || live_loudness_meter) {
+        on_warning("custom channel map is set; normalization, denoise, and the live loudness meter are not applied".to_string());
+    }
 
     // Resolve ffmpeg path: 1) /app/bin/ffmpeg 2) next to the executable 3) in the PATH
     let ffmpeg_path = find_ffmpeg_binary();
+    let audio_codec = if audio_bits == 24 { "pcm_s24le" } else { "pcm_s16le" };
+    let denoise_af = denoise.map(|m| format!("arnndn=m={}", m.display()));
+
+    if !channel_routes.is_empty() && stem_export {
+        // Video-only deliverable plus one discrete audio file per mapped group.
+        let mut video_cmd = Command::new(&ffmpeg_path);
+        video_cmd.arg("-y")
+        .arg("-i").arg(input)
+        .args(["-c:v", "dnxhd"])
+        .args(["-profile:v", profile])
+        .args(["-pix_fmt", select_pix_fmt(profile)])
+        .arg("-an");
+
+        if !preserve_fps {
+            video_cmd.args(["-r", &format!("{:.3}", target_fps)]);
+        }
+        if set_timecode {
+            video_cmd.args(["-timecode", timecode]);
+        }
+        if container == "fmp4" {
+            video_cmd.args(["-movflags", "+frag_keyframe+empty_moov+default_base_moof"])
+            .args(["-frag_duration", &(frag_duration_ms * 1000).to_string()]);
+        } else if container == "mp4_faststart" {
+            video_cmd.args(["-movflags", "+faststart"]);
+        }
+        let progress_path = next_progress_file_path();
+        video_cmd.arg("-progress").arg(&progress_path)
+        .arg("-stats_period").arg("0.5")
+        .arg(output);
+
+        let watch = spawn_progress_watch(progress_path, duration, on_progress);
+        let mut child = video_cmd.spawn().with_context(|| format!("Unable to start ffmpeg on {}", ffmpeg_path))?;
+        let status = child.wait()?;
+        watch.finish();
+        if !status.success() { anyhow::bail!("ffmpeg returned error code: {:?}", status.code()); }
+
+        let stem_dir = output.parent().unwrap_or_else(|| Path::new("."));
+        let stem_name = output.file_stem().and_then(|s| s.to_str()).unwrap_or("output");
+        for route in channel_routes {
+            let stem_path = stem_dir.join(format!("{}_{}.wav", stem_name, route.label));
+            let status = Command::new(&ffmpeg_path)
+            .arg("-y")
+            .arg("-i").arg(input)
+            .arg("-filter_complex").arg(pan_filter(route, 0))
+            .arg("-map").arg("[a0]")
+            .args(["-c:a", audio_codec])
+            .args(sample_rate_args(source_info.audio_sample_rate))
+            .arg(&stem_path)
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .status()
+            .with_context(|| format!("Unable to start ffmpeg for stem {}", route.label))?;
+            if !status.success() { anyhow::bail!("ffmpeg returned error code exporting stem {}: {:?}", route.label, status.code()); }
+        }
+        return Ok(());
+    }
+
+    let progress_path = next_progress_file_path();
 
     let base_cmd = |prog: &str| {
         let mut cmd = Command::new(prog);
@@ -602,9 +1369,26 @@ fn run_ffmpeg_with_progress(input: &Path, output: &Path, profile: &str, audio_bi
         .arg("-i").arg(input)
         .args(["-c:v", "dnxhd"])
         .args(["-profile:v", profile])
-        .args(["-pix_fmt", select_pix_fmt(profile)])
-        .args(["-c:a", if audio_bits == 24 { "pcm_s24le" } else { "pcm_s16le" }])
-        .args(["-ac", &audio_channels.to_string()]);
+        .args(["-pix_fmt", select_pix_fmt(profile)]);
+
+        if channel_routes.is_empty() {
+            cmd.args(["-c:a", audio_codec])
+            .args(["-ac", &audio_channels.to_string()])
+            .args(sample_rate_args(source_info.audio_sample_rate));
+        } else {
+            // Route the mapped input channels to their own output tracks via `pan`.
+            let filter = channel_routes.iter().enumerate()
+            .map(|(i, r)| pan_filter(r, i))
+            .collect::<Vec<_>>()
+            .join(";");
+            cmd.arg("-filter_complex").arg(filter)
+            .arg("-map").arg("0:v");
+            for i in 0..channel_routes.len() {
+                cmd.arg("-map").arg(format!("[a{}]", i));
+            }
+            cmd.args(["-c:a", audio_codec])
+            .args(sample_rate_args(source_info.audio_sample_rate));
+        }
 
         if !preserve_fps {
             cmd.args(["-r", &format!("{:.3}", target_fps)]);
@@ -614,106 +1398,516 @@ fn run_ffmpeg_with_progress(input: &Path, output: &Path, profile: &str, audio_bi
             cmd.args(["-timecode", timecode]);
         }
 
-        cmd.arg("-progress").arg("pipe:1")
-        .arg("-nostats")
+        // Denoise/meter -af: when normalization is also on, the normalize branch
+        // below builds a single combined -af chaining arnndn before loudnorm instead.
+        if !normalize_ebu_r128 && channel_routes.is_empty() {
+            let mut parts: Vec<String> = Vec::new();
+            if let Some(af) = &denoise_af { parts.push(af.clone()); }
+            if live_loudness_meter { parts.push("ebur128=peak=true".to_string()); }
+            if !parts.is_empty() {
+                cmd.arg("-af").arg(parts.join(","));
+            }
+        }
+
+        if container == "fmp4" {
+            cmd.args(["-movflags", "+frag_keyframe+empty_moov+default_base_moof"])
+            .args(["-frag_duration", &(frag_duration_ms * 1000).to_string()]);
+        } else if container == "mp4_faststart" {
+            // Non-fragmented MP4 with the moov atom relocated to the front, so
+            // playback and scrubbing can start before the whole file downloads.
+            cmd.args(["-movflags", "+faststart"]);
+        }
+
+        cmd.arg("-progress").arg(&progress_path)
+        .arg("-stats_period").arg("0.5")
         .arg(output)
-        .stdout(std::process::Stdio::piped())
-        .stderr(std::process::Stdio::null());
+        .stderr(if live_loudness_meter { std::process::Stdio::piped() } else { std::process::Stdio::inherit() });
         cmd
     };
 
-    // Normalization EBU R128 (optional)
-    if normalize_ebu_r128 {
-        if let Some(params) = measure_loudness_params(&ffmpeg_path, input)? {
-            // apply second pass with measured_* params
+    // Normalization EBU R128 (optional; not combined with custom channel routing,
+    // since that already occupies the filter graph with `pan`).
+    if normalize_ebu_r128 && channel_routes.is_empty() {
+        if let Some(params) = measure_loudness_params(&ffmpeg_path, input, loudness_i, loudness_tp, loudness_lra, loudness_target, denoise_af.as_deref())? {
+            if params.dynamic_fallback {
+                on_warning("source too short/quiet for linear normalization; loudnorm fell back to dynamic mode".to_string());
+            }
+            // apply second pass with measured_* params, denoising ahead of loudnorm
             let mut cmd = base_cmd(&ffmpeg_path);
-            let af = format!(
-                "loudnorm=I=-23:TP=-2:LRA=7:measured_I={}:measured_LRA={}:measured_TP={}:measured_thresh={}:print_format=summary",
-                params.i, params.lra, params.tp, params.thresh
+            let (target_i, target_tp, target_lra) = params.target.preset_params().unwrap_or((loudness_i, loudness_tp, loudness_lra));
+            let loudnorm = format!(
+                "loudnorm=I={}:TP={}:LRA={}:measured_I={}:measured_TP={}:measured_LRA={}:measured_thresh={}:offset={}:linear=true",
+                target_i, target_tp, target_lra,
+                params.measured_i, params.measured_tp, params.measured_lra, params.measured_thresh, params.target_offset,
                 );
-            cmd.arg("-af").arg(af);
+            let mut parts = vec![loudnorm];
+            if let Some(prefix) = &denoise_af { parts.insert(0, prefix.clone()); }
+            if live_loudness_meter { parts.push("ebur128=peak=true".to_string()); }
+            cmd.arg("-af").arg(parts.join(","));
+            let watch = spawn_progress_watch(progress_path, duration, on_progress);
             let mut child = cmd.spawn().with_context(|| "Failed to start ffmpeg (normalization)")?;
-            attach_progress(&mut child, duration, &mut on_progress)?;
+            if live_loudness_meter {
+                if let Some(cb) = on_loudness.take() {
+                    attach_loudness_metering(&mut child, cb);
+                }
+            }
             let status = child.wait()?;
+            watch.finish();
             if !status.success() { anyhow::bail!("ffmpeg returned error code on normalization pass: {:?}", status.code()); }
             return Ok(());
         }
     }
 
     // No normalization: direct execution
+    let watch = spawn_progress_watch(progress_path, duration, on_progress);
     let mut child = base_cmd(&ffmpeg_path)
     .spawn()
     .with_context(|| format!("Unable to start ffmpeg on {}", ffmpeg_path))?;
 
-    attach_progress(&mut child, duration, &mut on_progress)?;
+    if live_loudness_meter {
+        if let Some(cb) = on_loudness.take() {
+            attach_loudness_metering(&mut child, cb);
+        }
+    }
 
     let status = child.wait()?;
+    watch.finish();
     if !status.success() {
         anyhow::bail!("ffmpeg returned error code: {:?}", status.code());
     }
     Ok(())
 }
 
-fn attach_progress(child: &mut std::process::Child, duration: f64, on_progress: &mut impl FnMut(f64)) -> Result<()> {
-    if let Some(out) = child.stdout.take() {
-        use std::io::{BufRead, BufReader};
-        let mut reader = BufReader::new(out);
-        let mut line = String::new();
-        let mut sent_indeterminate = false;
-        let known_duration = duration > 0.0;
-        while let Ok(n) = reader.read_line(&mut line) {
-            // Log raw progress line for debugging (trim to avoid spam)
-            if n > 0 {
-                let dbg = line.trim();
-                if !dbg.is_empty() {
-                    eprintln!("[DEBUG] ffmpeg progress: {}", dbg);
+// Reads the `ebur128` filter's stderr lines off a spare thread and reports
+// momentary/short-term/integrated/LRA LUFS values as the encode runs.
+fn attach_loudness_metering(child: &mut std::process::Child, mut on_loudness: Box<dyn FnMut(f64, f64, f64, f64) + Send>) {
+    if let Some(err) = child.stderr.take() {
+        std::thread::spawn(move || {
+            use std::io::{BufRead, BufReader};
+            let reader = BufReader::new(err);
+            for line in reader.lines() {
+                let line = match line {
+                    Ok(l) => l,
+                    Err(_) => break,
+                };
+                if let Some((m, s, i, lra)) = parse_ebur128_line(&line) {
+                    on_loudness(m, s, i, lra);
                 }
             }
-            if n == 0 { break; }
-            // Lines formatted: key=value. Note: out_time_ms is microseconds despite the name.
-            // Prefer out_time_us if present; fall back to out_time_ms.
-            let mut handled_time = false;
-            if let Some(rest) = line.strip_prefix("out_time_us=") {
-                if let Ok(us) = rest.trim().parse::<u64>() {
-                    handled_time = true;
-                    if known_duration {
-                        let frac = (us as f64 / 1_000_000.0) / duration;
-                        eprintln!("[DEBUG] computed fraction {:.3}", frac);
-                        on_progress(frac.clamp(0.0, 0.999));
-                    } else if !sent_indeterminate {
-                        eprintln!("[DEBUG] unknown duration, switching to indeterminate pulsing");
-                        on_progress(-1.0);
-                        sent_indeterminate = true;
-                    }
-                }
+        });
+    }
+}
+
+// Parses a verbose `ebur128` log line, e.g.
+// "t: 12.3 M: -18.0 S: -19.2 I: -20.1 LUFS  LRA: 5.3 LRA low: -30.0 LRA high: -20.0"
+fn parse_ebur128_line(line: &str) -> Option<(f64, f64, f64, f64)> {
+    let m = extract_labeled_number(line, "M:")?;
+    let s = extract_labeled_number(line, "S:")?;
+    let i = extract_labeled_number(line, "I:")?;
+    let lra = extract_labeled_number(line, "LRA:")?;
+    Some((m, s, i, lra))
+}
+
+fn extract_labeled_number(line: &str, label: &str) -> Option<f64> {
+    let pos = line.find(label)?;
+    let rest = line[pos + label.len()..].trim_start();
+    let mut num = String::new();
+    for c in rest.chars() {
+        if c.is_ascii_digit() || c == '-' || c == '.' {
+            num.push(c);
+        } else if !num.is_empty() {
+            break;
+        }
+    }
+    num.parse::<f64>().ok()
+}
+
+// `-ar <rate>` when the source's sample rate was probed, otherwise no args at
+// all, so ffmpeg falls back to its own default instead of being told to
+// resample to a rate we never actually measured.
+fn sample_rate_args(rate: Option<u32>) -> Vec<String> {
+    match rate {
+        Some(r) => vec!["-ar".to_string(), r.to_string()],
+        None => Vec::new(),
+    }
+}
+
+// Builds a `pan` filtergraph segment routing a route's input channels into
+// a single labeled output stream `[a<idx>]`, e.g. "[0:a]pan=2c|c0=c0|c1=c1[a0]".
+fn pan_filter(route: &ChannelRoute, idx: usize) -> String {
+    let n = route.input_channels.len();
+    let chans: Vec<String> = route.input_channels.iter().enumerate()
+    .map(|(out_ch, in_ch)| format!("c{}=c{}", out_ch, in_ch))
+    .collect();
+    format!("[0:a]pan={}c|{}[a{}]", n, chans.join("|"), idx)
+}
+
+// HLS delivery mode: fMP4 media segments plus an index.m3u8 playlist, written
+// into `out_dir` alongside the usual single-file mov/mxf/fmp4 path. Takes the
+// same denoise/normalize/live-meter audio options as `run_ffmpeg_with_progress`
+// so enabling them on the controls doesn't silently no-op just because HLS
+// was picked as the output mode.
+fn run_ffmpeg_hls_with_progress(input: &Path, out_dir: &Path, profile: &str, audio_bits: u32, audio_channels: u32, preserve_fps: bool, target_fps: f64, set_timecode: bool, timecode: &str, hls_segment_secs: f64, normalize_ebu_r128: bool, loudness_i: f64, loudness_tp: f64, loudness_lra: f64, loudness_target: LoudnessTarget, denoise: Option<&Path>, live_loudness_meter: bool, mut on_loudness: Option<Box<dyn FnMut(f64, f64, f64, f64) + Send>>, mut on_warning: impl FnMut(String) + Send + 'static, on_progress: impl FnMut(f64) + Send + 'static) -> Result<()> {
+    let source_info = probe_source_info(input).unwrap_or_default();
+    let duration = source_info.duration_secs.unwrap_or(0.0);
+    if let Some(warning) = bit_depth_downsample_warning(profile, source_info.bit_depth) {
+        on_warning(warning);
+    }
+    let ffmpeg_path = find_ffmpeg_binary();
+    std::fs::create_dir_all(out_dir).with_context(|| format!("Unable to create HLS output dir {}", out_dir.display()))?;
+    let progress_path = next_progress_file_path();
+    let audio_codec = if audio_bits == 24 { "pcm_s24le" } else { "pcm_s16le" };
+    let denoise_af = denoise.map(|m| format!("arnndn=m={}", m.display()));
+
+    let base_cmd = |prog: &str| {
+        let mut cmd = Command::new(prog);
+        cmd.arg("-y")
+        .arg("-i").arg(input)
+        .args(["-c:v", "dnxhd"])
+        .args(["-profile:v", profile])
+        .args(["-pix_fmt", select_pix_fmt(profile)])
+        .args(["-c:a", audio_codec])
+        .args(["-ac", &audio_channels.to_string()])
+        .args(sample_rate_args(source_info.audio_sample_rate));
+
+        if !preserve_fps {
+            cmd.args(["-r", &format!("{:.3}", target_fps)]);
+        }
+
+        if set_timecode {
+            // Stamps the editorial timecode on the video stream, same as the
+            // single-file path, and seeds `creation_time` from that same
+            // timecode's time-of-day (anchored at the Unix epoch date, since
+            // the source's real capture date isn't known here) so the HLS
+            // muxer's first PROGRAM-DATE-TIME tag below reflects the source
+            // timecode instead of the wall-clock moment encoding starts.
+            cmd.args(["-timecode", timecode])
+            .args(["-metadata:s:v", &format!("timecode={}", timecode)]);
+            if let Some(creation_time) = timecode_to_creation_time(timecode, target_fps) {
+                cmd.args(["-metadata", &format!("creation_time={}", creation_time)]);
             }
-            if !handled_time {
-                if let Some(rest) = line.strip_prefix("out_time_ms=") {
-                    if let Ok(us_misnamed) = rest.trim().parse::<u64>() {
-                        // Despite the name, this is also microseconds.
-                        if known_duration {
-                            let frac = (us_misnamed as f64 / 1_000_000.0) / duration;
-                            eprintln!("[DEBUG] computed fraction {:.3}", frac);
-                            on_progress(frac.clamp(0.0, 0.999));
-                        } else if !sent_indeterminate {
-                            eprintln!("[DEBUG] unknown duration, switching to indeterminate pulsing");
-                            on_progress(-1.0);
-                            sent_indeterminate = true;
-                        }
-                    }
-                }
+        }
+
+        cmd.args(["-f", "hls"])
+        .args(["-hls_segment_type", "fmp4"])
+        .args(["-hls_time", &hls_segment_secs.to_string()])
+        .args(["-hls_flags", "independent_segments+program_date_time"])
+        .args(["-hls_playlist_type", "vod"])
+        .arg("-hls_segment_filename").arg(out_dir.join("seg_%05d.m4s"))
+        .arg("-progress").arg(&progress_path)
+        .arg("-stats_period").arg("0.5")
+        .arg(out_dir.join("index.m3u8"))
+        .stderr(if live_loudness_meter { std::process::Stdio::piped() } else { std::process::Stdio::inherit() });
+        cmd
+    };
+
+    // Normalization EBU R128 (optional), mirroring the single-file path: a
+    // measure pass first, then the real encode with measured_* loudnorm params.
+    if normalize_ebu_r128 {
+        if let Some(params) = measure_loudness_params(&ffmpeg_path, input, loudness_i, loudness_tp, loudness_lra, loudness_target, denoise_af.as_deref())? {
+            if params.dynamic_fallback {
+                on_warning("source too short/quiet for linear normalization; loudnorm fell back to dynamic mode".to_string());
             }
-            if line.starts_with("progress=end") {
-                eprintln!("[DEBUG] ffmpeg progress=end");
-                on_progress(1.0);
+            let mut cmd = base_cmd(&ffmpeg_path);
+            let (target_i, target_tp, target_lra) = params.target.preset_params().unwrap_or((loudness_i, loudness_tp, loudness_lra));
+            let loudnorm = format!(
+                "loudnorm=I={}:TP={}:LRA={}:measured_I={}:measured_TP={}:measured_LRA={}:measured_thresh={}:offset={}:linear=true",
+                target_i, target_tp, target_lra,
+                params.measured_i, params.measured_tp, params.measured_lra, params.measured_thresh, params.target_offset,
+                );
+            let mut parts = vec![loudnorm];
+            if let Some(prefix) = &denoise_af { parts.insert(0, prefix.clone()); }
+            if live_loudness_meter { parts.push("ebur128=peak=true".to_string()); }
+            cmd.arg("-af").arg(parts.join(","));
+
+            let watch = spawn_progress_watch(progress_path, duration, on_progress);
+            let mut child = cmd.spawn().with_context(|| "Failed to start ffmpeg (HLS normalization)")?;
+            if live_loudness_meter {
+                if let Some(cb) = on_loudness.take() {
+                    attach_loudness_metering(&mut child, cb);
+                }
             }
-            line.clear();
+            let status = child.wait()?;
+            watch.finish();
+            if !status.success() { anyhow::bail!("ffmpeg returned error code on HLS normalization pass: {:?}", status.code()); }
+            return Ok(());
         }
     }
 
+    // No normalization: denoise/live-meter (if any) still ride along as a
+    // plain -af chain on the direct encode.
+    let mut cmd = base_cmd(&ffmpeg_path);
+    let mut parts: Vec<String> = Vec::new();
+    if let Some(af) = &denoise_af { parts.push(af.clone()); }
+    if live_loudness_meter { parts.push("ebur128=peak=true".to_string()); }
+    if !parts.is_empty() {
+        cmd.arg("-af").arg(parts.join(","));
+    }
+
+    let watch = spawn_progress_watch(progress_path, duration, on_progress);
+    let mut child = cmd.spawn().with_context(|| format!("Unable to start ffmpeg on {}", ffmpeg_path))?;
+    if live_loudness_meter {
+        if let Some(cb) = on_loudness.take() {
+            attach_loudness_metering(&mut child, cb);
+        }
+    }
+
+    let status = child.wait()?;
+    watch.finish();
+    if !status.success() {
+        anyhow::bail!("ffmpeg returned error code on HLS pass: {:?}", status.code());
+    }
     Ok(())
 }
 
+// Stitches an optional intro, the queued clips, and an optional outro into a
+// single DNxHR master in one ffmpeg invocation, the way a render pipeline
+// composes intro -> body -> outro with short fades. `transition` is "none"
+// for a hard cut (built with the `concat` filter) or "fade"/"fadeblack" for
+// an `xfade`/`acrossfade` crossfade of `transition_secs` at each boundary.
+// `container`/`frag_duration_ms` pick the master's extension and movflags the
+// same way the single-file path does; normalize/denoise/timecode are not
+// supported here (the UI disables them while concat mode is selected).
+fn run_ffmpeg_concat_with_progress(
+    intro: Option<&Path>,
+    clips: &[PathBuf],
+    outro: Option<&Path>,
+    transition: &str,
+    transition_secs: f64,
+    output: &Path,
+    profile: &str,
+    container: &str,
+    frag_duration_ms: u32,
+    audio_bits: u32,
+    audio_channels: u32,
+    on_progress: impl FnMut(f64) + Send + 'static,
+    ) -> Result<()> {
+    let mut inputs: Vec<PathBuf> = Vec::new();
+    if let Some(i) = intro { inputs.push(i.to_path_buf()); }
+    inputs.extend(clips.iter().cloned());
+    if let Some(o) = outro { inputs.push(o.to_path_buf()); }
+    if inputs.is_empty() {
+        anyhow::bail!("no clips to concatenate");
+    }
+
+    let ffmpeg_path = find_ffmpeg_binary();
+    let audio_codec = if audio_bits == 24 { "pcm_s24le" } else { "pcm_s16le" };
+
+    let probes: Vec<SourceInfo> = inputs.iter().map(|p| probe_source_info(p).unwrap_or_default()).collect();
+    let durations: Vec<f64> = probes.iter().map(|p| p.duration_secs.unwrap_or(0.0)).collect();
+    let source_bit_depth = probes.first().and_then(|p| p.bit_depth);
+    let source_sample_rate = probes.first().and_then(|p| p.audio_sample_rate);
+    if let Some(warning) = bit_depth_downsample_warning(profile, source_bit_depth) {
+        eprintln!("warning: {}", warning);
+    }
+
+    let n = inputs.len();
+    let use_transition = transition != "none" && n > 1;
+    let total_duration = if use_transition {
+        durations.iter().sum::<f64>() - transition_secs * (n - 1) as f64
+    } else {
+        durations.iter().sum::<f64>()
+    };
+
+    let (filter_complex, final_v, final_a) = if use_transition {
+        let xfade_name = if transition == "fadeblack" { "fadeblack" } else { "fade" };
+        let mut chain = String::new();
+        let mut cumulative = durations[0];
+        let mut prev_v = "0:v".to_string();
+        let mut prev_a = "0:a".to_string();
+        for i in 1..n {
+            let offset = (cumulative - transition_secs).max(0.0);
+            let out_v = format!("v{:02}", i);
+            let out_a = format!("a{:02}", i);
+            chain.push_str(&format!(
+                "[{}][{}:v]xfade=transition={}:duration={}:offset={}[{}];",
+                prev_v, i, xfade_name, transition_secs, offset, out_v
+                ));
+            chain.push_str(&format!(
+                "[{}][{}:a]acrossfade=d={}[{}];",
+                prev_a, i, transition_secs, out_a
+                ));
+            cumulative += durations[i] - transition_secs;
+            prev_v = out_v;
+            prev_a = out_a;
+        }
+        chain.pop(); // drop the trailing ';'
+        (chain, prev_v, prev_a)
+    } else {
+        let mut labels = String::new();
+        for i in 0..n {
+            labels.push_str(&format!("[{}:v][{}:a]", i, i));
+        }
+        (format!("{}concat=n={}:v=1:a=1[outv][outa]", labels, n), "outv".to_string(), "outa".to_string())
+    };
+
+    let mut cmd = Command::new(&ffmpeg_path);
+    cmd.arg("-y");
+    for input in &inputs {
+        cmd.arg("-i").arg(input);
+    }
+    cmd.arg("-filter_complex").arg(&filter_complex)
+    .arg("-map").arg(format!("[{}]", final_v))
+    .arg("-map").arg(format!("[{}]", final_a))
+    .args(["-c:v", "dnxhd"])
+    .args(["-profile:v", profile])
+    .args(["-pix_fmt", select_pix_fmt(profile)])
+    .args(["-c:a", audio_codec])
+    .args(["-ac", &audio_channels.to_string()])
+    .args(sample_rate_args(source_sample_rate));
+
+    if container == "fmp4" {
+        cmd.args(["-movflags", "+frag_keyframe+empty_moov+default_base_moof"])
+        .args(["-frag_duration", &(frag_duration_ms * 1000).to_string()]);
+    } else if container == "mp4_faststart" {
+        cmd.args(["-movflags", "+faststart"]);
+    }
+
+    let progress_path = next_progress_file_path();
+    cmd.arg("-progress").arg(&progress_path)
+    .arg("-stats_period").arg("0.5")
+    .arg(output)
+    .stderr(std::process::Stdio::inherit());
+
+    let watch = spawn_progress_watch(progress_path, total_duration, on_progress);
+    let mut child = cmd.spawn().with_context(|| format!("Unable to start ffmpeg on {}", ffmpeg_path))?;
+    let status = child.wait()?;
+    watch.finish();
+    if !status.success() {
+        anyhow::bail!("ffmpeg returned error code on concat pass: {:?}", status.code());
+    }
+    Ok(())
+}
+
+// Tracks a background thread tailing an ffmpeg `-progress` file for a single
+// job. Encode progress no longer rides the child's stdout pipe (fragile: any
+// other writer to stdout, or ffmpeg buffering oddly, could stall or corrupt
+// it); instead ffmpeg appends `key=value` lines to `path` and a `notify`
+// watcher wakes this thread whenever the file grows, so the child's own
+// stdio stays inherited and its real errors are visible in the terminal.
+struct ProgressWatch {
+    done: Arc<AtomicBool>,
+    handle: std::thread::JoinHandle<()>,
+    path: PathBuf,
+}
+
+impl ProgressWatch {
+    // Signals the tailing thread to do one last read and stop, then removes
+    // the scratch file. Call after the child has exited.
+    fn finish(self) {
+        self.done.store(true, Ordering::SeqCst);
+        let _ = self.handle.join();
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+static PROGRESS_FILE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+fn next_progress_file_path() -> PathBuf {
+    let n = PROGRESS_FILE_COUNTER.fetch_add(1, Ordering::Relaxed);
+    std::env::temp_dir().join(format!("dnxhd_progress_{}_{}.txt", std::process::id(), n))
+}
+
+fn spawn_progress_watch(path: PathBuf, duration: f64, mut on_progress: impl FnMut(f64) + Send + 'static) -> ProgressWatch {
+    // Create the file up front so the watcher has something to attach to
+    // before ffmpeg itself opens it.
+    let _ = std::fs::File::create(&path);
+
+    let done = Arc::new(AtomicBool::new(false));
+    let done_bg = Arc::clone(&done);
+    let watch_path = path.clone();
+
+    let handle = std::thread::spawn(move || {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let mut watcher = match notify::recommended_watcher(move |res| { let _ = tx.send(res); }) {
+            Ok(w) => w,
+            Err(e) => { eprintln!("[DEBUG] progress watcher init failed: {e}"); return; }
+        };
+        if let Err(e) = watcher.watch(&watch_path, RecursiveMode::NonRecursive) {
+            eprintln!("[DEBUG] progress watcher attach failed: {e}");
+        }
+
+        let mut offset: u64 = 0;
+        let mut sent_indeterminate = false;
+        loop {
+            if read_progress_chunk(&watch_path, &mut offset, duration, &mut sent_indeterminate, &mut on_progress) {
+                break; // saw progress=end
+            }
+            if done_bg.load(Ordering::SeqCst) {
+                // ffmpeg exited (cleanly or not); take one last look in case the
+                // final lines landed after our previous read.
+                let _ = read_progress_chunk(&watch_path, &mut offset, duration, &mut sent_indeterminate, &mut on_progress);
+                break;
+            }
+            let _ = rx.recv_timeout(std::time::Duration::from_millis(200));
+        }
+    });
+
+    ProgressWatch { done, handle, path }
+}
+
+// Reads whatever has been appended to the progress file since `offset`,
+// forwarding `out_time_us=`/`progress=end` the same way the old stdout
+// parser did, and advances `offset` past what was read. Returns true once
+// `progress=end` is seen.
+fn read_progress_chunk(path: &Path, offset: &mut u64, duration: f64, sent_indeterminate: &mut bool, on_progress: &mut impl FnMut(f64)) -> bool {
+    use std::io::{Read, Seek, SeekFrom};
+    let mut file = match std::fs::File::open(path) {
+        Ok(f) => f,
+        Err(_) => return false,
+    };
+    if file.seek(SeekFrom::Start(*offset)).is_err() {
+        return false;
+    }
+    let mut buf = String::new();
+    if file.read_to_string(&mut buf).is_err() {
+        return false;
+    }
+    *offset += buf.len() as u64;
+
+    let known_duration = duration > 0.0;
+    let mut saw_end = false;
+    for line in buf.lines() {
+        if let Some(rest) = line.strip_prefix("out_time_us=") {
+            if let Ok(us) = rest.trim().parse::<u64>() {
+                if known_duration {
+                    let frac = (us as f64 / 1_000_000.0) / duration;
+                    on_progress(frac.clamp(0.0, 0.999));
+                } else if !*sent_indeterminate {
+                    on_progress(-1.0);
+                    *sent_indeterminate = true;
+                }
+            }
+        } else if line.starts_with("progress=end") {
+            on_progress(1.0);
+            saw_end = true;
+        }
+    }
+    saw_end
+}
+
+// Turns an editorial `HH:MM:SS:FF` timecode into an ISO-8601 `creation_time`
+// value ffmpeg's HLS muxer can anchor PROGRAM-DATE-TIME to: the date is
+// pinned to the Unix epoch (we only know the timecode's time-of-day, not the
+// source's real capture date) and the frame count is converted to a
+// fractional second using the output frame rate.
+fn timecode_to_creation_time(timecode: &str, fps: f64) -> Option<String> {
+    let parts: Vec<&str> = timecode.split(':').collect();
+    if parts.len() != 4 || fps <= 0.0 {
+        return None;
+    }
+    let h: u32 = parts[0].parse().ok()?;
+    let m: u32 = parts[1].parse().ok()?;
+    let s: u32 = parts[2].parse().ok()?;
+    let f: u32 = parts[3].parse().ok()?;
+    let micros = ((f as f64 / fps) * 1_000_000.0).round() as u32;
+    Some(format!("1970-01-01T{:02}:{:02}:{:02}.{:06}Z", h, m, s, micros))
+}
+
+// ffmpeg's dnxhd encoder fixes the pixel format per DNxHR profile, not per
+// source: dnxhr_hqx/444 only ever encode 10-bit, and dnxhr_lb/sq/hq only
+// ever encode 8-bit yuv422p (asking for yuv422p10le there is rejected).
+// There is no profile here where the source's bit depth decides the format.
 fn select_pix_fmt(profile: &str) -> &'static str {
     match profile {
         "dnxhr_hqx" => "yuv422p10le",
@@ -722,6 +1916,21 @@ fn select_pix_fmt(profile: &str) -> &'static str {
     }
 }
 
+// `select_pix_fmt`'s 8-bit-only profiles silently lose bit depth on a 10-bit
+// source; surfaces that loss as a warning instead of nothing at all.
+fn bit_depth_downsample_warning(profile: &str, source_bit_depth: Option<u32>) -> Option<String> {
+    let profile_is_8bit_only = !matches!(profile, "dnxhr_hqx" | "dnxhr_444");
+    let depth = source_bit_depth.unwrap_or(8);
+    if profile_is_8bit_only && depth > 8 {
+        Some(format!(
+            "source is {}-bit but profile {} only encodes 8-bit yuv422p; picture will be downsampled",
+            depth, profile
+        ))
+    } else {
+        None
+    }
+}
+
 fn find_ffmpeg_binary() -> String {
     // Priority: Flatpak (/app/bin/ffmpeg) -> in side of executable -> PATH
     let flatpak_ffmpeg = PathBuf::from("/app/bin/ffmpeg");
@@ -739,16 +1948,80 @@ fn find_ffmpeg_binary() -> String {
     "ffmpeg".to_string()
 }
 
-fn probe_duration_secs(input: &Path) -> Option<f64> {
+// One row of `ffprobe -show_streams -of json`'s `streams` array. Only the
+// fields we actually consult are declared; serde ignores the rest.
+#[derive(Deserialize, Debug)]
+struct FfprobeStream {
+    codec_type: String,
+    pix_fmt: Option<String>,
+    bits_per_raw_sample: Option<String>,
+    sample_rate: Option<String>,
+    duration: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct FfprobeOutput {
+    streams: Vec<FfprobeStream>,
+}
+
+// Metadata pulled from a single `ffprobe -show_streams -of json` call: the
+// first video stream's bit depth, the first audio stream's sample rate, and
+// the longest stream duration. Replaces the old bare `format=duration` text
+// scrape and lets callers warn when an 8-bit-only profile downsamples the
+// source and preserve the source's audio sample rate. The DNxHR pixel format
+// is not chosen from this; `select_pix_fmt` keys off the profile alone.
+#[derive(Default)]
+struct SourceInfo {
+    duration_secs: Option<f64>,
+    bit_depth: Option<u32>,
+    audio_sample_rate: Option<u32>,
+}
+
+fn probe_source_info(input: &Path) -> Result<SourceInfo> {
     let ffprobe = find_ffprobe_binary();
-    let out = Command::new(ffprobe)
-    .args(["-v", "error", "-show_entries", "format=duration", "-of", "default=nw=1:nk=1"])
+    let out = Command::new(&ffprobe)
+    .args(["-v", "error", "-show_streams"])
+    .args(["-show_entries", "stream=codec_type,pix_fmt,bits_per_raw_sample,sample_rate,duration"])
+    .args(["-of", "json"])
     .arg(input)
     .output()
-    .ok()?;
-    if !out.status.success() { return None; }
-    let s = String::from_utf8_lossy(&out.stdout);
-    s.trim().parse::<f64>().ok()
+    .with_context(|| format!("Unable to run ffprobe on {}", input.display()))?;
+    if !out.status.success() {
+        anyhow::bail!("ffprobe returned error code {:?} for {}", out.status.code(), input.display());
+    }
+
+    let parsed: FfprobeOutput = serde_json::from_slice(&out.stdout)
+    .with_context(|| format!("Failed to parse ffprobe JSON for {}", input.display()))?;
+
+    let video = parsed.streams.iter().find(|s| s.codec_type == "video");
+    let audio = parsed.streams.iter().find(|s| s.codec_type == "audio");
+
+    let bit_depth = video
+    .and_then(|v| v.bits_per_raw_sample.as_deref())
+    .and_then(|s| s.parse::<u32>().ok())
+    .or_else(|| video.and_then(|v| v.pix_fmt.as_deref()).map(pix_fmt_bit_depth));
+
+    let duration_secs = parsed.streams.iter()
+    .filter_map(|s| s.duration.as_deref())
+    .filter_map(|s| s.parse::<f64>().ok())
+    .fold(None, |acc: Option<f64>, d| Some(acc.map_or(d, |a| a.max(d))));
+
+    Ok(SourceInfo {
+        duration_secs,
+        bit_depth,
+        audio_sample_rate: audio.and_then(|a| a.sample_rate.as_deref()).and_then(|s| s.parse::<u32>().ok()),
+    })
+}
+
+// Falls back on the pixel format name when `bits_per_raw_sample` isn't
+// reported by the decoder: anything ending `10le`/`10be` is 10-bit, the rest
+// is treated as 8-bit.
+fn pix_fmt_bit_depth(pix_fmt: &str) -> u32 {
+    if pix_fmt.ends_with("10le") || pix_fmt.ends_with("10be") { 10 } else { 8 }
+}
+
+fn probe_duration_secs(input: &Path) -> Option<f64> {
+    probe_source_info(input).ok().and_then(|info| info.duration_secs)
 }
 
 fn find_ffprobe_binary() -> String {
@@ -767,19 +2040,95 @@ fn find_ffprobe_binary() -> String {
     "ffprobe".to_string()
 }
 
-struct LoudnessParams { i: f64, lra: f64, tp: f64, thresh: f64 }
+static THUMBNAIL_FILE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+// Unique per invocation (pid + monotonic counter + the seek position), the
+// same way `next_progress_file_path()` avoids collisions for ffmpeg's
+// `-progress` file: scrubbing fires several overlapping thumbnail requests,
+// and two of them sharing one path would let the `Picture` load a
+// half-written or stale PNG.
+fn next_thumbnail_file_path(at_secs: f64) -> PathBuf {
+    let n = THUMBNAIL_FILE_COUNTER.fetch_add(1, Ordering::Relaxed);
+    std::env::temp_dir().join(format!(
+        "dnxhd_preview_{}_{}_{:.3}.png",
+        std::process::id(), n, at_secs.max(0.0)
+        ))
+}
+
+// Off-UI-thread thumbnail generation for the preview pane. Decodes a single
+// frame at `at_secs` into a uniquely-named temp PNG and hands the path back
+// over `tx`. `generation`/`current_generation` let a newer scrub request
+// supersede an older one still in flight: a stale result is discarded (and
+// its temp file cleaned up) instead of racing the latest one into the UI.
+fn spawn_thumbnail(input: PathBuf, at_secs: f64, tx: glib::Sender<PathBuf>, generation: u64, current_generation: Arc<AtomicU64>) {
+    std::thread::spawn(move || {
+        if current_generation.load(Ordering::SeqCst) != generation {
+            return; // already superseded before ffmpeg even started
+        }
+
+        let ffmpeg = find_ffmpeg_binary();
+        let png_path = next_thumbnail_file_path(at_secs);
+        let status = Command::new(&ffmpeg)
+        .arg("-y")
+        .args(["-ss", &format!("{:.3}", at_secs.max(0.0))])
+        .arg("-i").arg(&input)
+        .args(["-frames:v", "1"])
+        .args(["-vf", "scale=320:-2"])
+        .args(["-f", "image2"])
+        .arg(&png_path)
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status();
+
+        match status {
+            Ok(s) if s.success() => {
+                if current_generation.load(Ordering::SeqCst) == generation {
+                    let _ = tx.send(png_path);
+                } else {
+                    let _ = std::fs::remove_file(&png_path);
+                }
+            }
+            Ok(s) => {
+                eprintln!("[DEBUG] thumbnail ffmpeg exited with {:?}", s.code());
+                let _ = std::fs::remove_file(&png_path);
+            }
+            Err(e) => eprintln!("[DEBUG] failed to spawn thumbnail ffmpeg: {e}"),
+        }
+    });
+}
+
+struct LoudnessParams {
+    measured_i: f64,
+    measured_lra: f64,
+    measured_tp: f64,
+    measured_thresh: f64,
+    target_offset: f64,
+    // true when loudnorm reported `normalization_type: "dynamic"`, i.e. the
+    // source was too short or too quiet for a reliable linear pass.
+    dynamic_fallback: bool,
+    // The delivery target this measurement was taken against, so the second
+    // pass applies the same preset's I/TP/LRA rather than re-deriving them.
+    target: LoudnessTarget,
+}
 
-fn measure_loudness_params(ffmpeg: &str, input: &Path) -> Result<Option<LoudnessParams>> {
-    // First pass: just measure, no way out
+fn measure_loudness_params(ffmpeg: &str, input: &Path, target_i: f64, target_tp: f64, target_lra: f64, target: LoudnessTarget, denoise_af: Option<&str>) -> Result<Option<LoudnessParams>> {
+    // First pass: measure only, write nothing out. If denoising is enabled the
+    // measurement runs on the cleaned signal so the targets it computes apply
+    // to what the second pass will actually hear.
+    let loudnorm = format!("loudnorm=I={}:TP={}:LRA={}:print_format=json", target_i, target_tp, target_lra);
+    let af = match denoise_af {
+        Some(prefix) => format!("{},{}", prefix, loudnorm),
+        None => loudnorm,
+    };
     let child = Command::new(ffmpeg)
     .arg("-hide_banner")
     .arg("-i").arg(input)
-    .arg("-af").arg("loudnorm=I=-23:TP=-2:LRA=7:print_format=json")
+    .arg("-af").arg(af)
     .arg("-f").arg("null").arg("-")
     .stdout(std::process::Stdio::piped())
     .stderr(std::process::Stdio::piped())
     .spawn()
-    .with_context(|| "Falha ao iniciar ffmpeg para medição EBU R128")?;
+    .with_context(|| "Failed to start ffmpeg for EBU R128 measurement")?;
 
     let output = child.wait_with_output()?;
     if !output.status.success() {
@@ -789,38 +2138,32 @@ fn measure_loudness_params(ffmpeg: &str, input: &Path) -> Result<Option<Loudness
     txt.push_str(&String::from_utf8_lossy(&output.stdout));
     txt.push_str(&String::from_utf8_lossy(&output.stderr));
 
-    // Extract JSON block
-    if let Some(start) = txt.find('{') {
-        if let Some(end) = txt.rfind('}') {
-            let json_str = &txt[start..=end];
-            // Rudimentary Parse of required fields
-            let i = extract_json_number(json_str, "input_i").or_else(|| extract_json_number(json_str, "measured_I")).unwrap_or(-23.0);
-            let lra = extract_json_number(json_str, "input_lra").or_else(|| extract_json_number(json_str, "measured_LRA")).unwrap_or(7.0);
-            let tp = extract_json_number(json_str, "input_tp").or_else(|| extract_json_number(json_str, "measured_TP")).unwrap_or(-2.0);
-            let thresh = extract_json_number(json_str, "input_thresh").or_else(|| extract_json_number(json_str, "measured_thresh")).unwrap_or(-34.0);
-            return Ok(Some(LoudnessParams { i, lra, tp, thresh }));
-        }
-    }
-    Ok(None)
-}
-
-fn extract_json_number(s: &str, key: &str) -> Option<f64> {
-    // Busca "key": valor
-    let pat = format!("\"{}\"", key);
-    if let Some(pos) = s.find(&pat) {
-        let rest = &s[pos + pat.len()..];
-        if let Some(colon) = rest.find(':') {
-            let rest = &rest[colon + 1..];
-            let mut num = String::new();
-            for c in rest.chars() {
-                if c.is_ascii_digit() || c == '-' || c == '.' {
-                    num.push(c);
-                } else if !num.is_empty() {
-                    break;
-                }
-            }
-            if let Ok(v) = num.parse::<f64>() { return Some(v); }
-        }
-    }
-    None
+    // Extract JSON block and parse it properly instead of scanning characters
+    // for each key by hand.
+    let start = txt.find('{').with_context(|| "loudnorm measurement produced no JSON block")?;
+    let end = txt.rfind('}').with_context(|| "loudnorm measurement produced no JSON block")?;
+    let measurement: LoudnormMeasurement = serde_json::from_str(&txt[start..=end])
+    .with_context(|| "Failed to parse loudnorm measurement JSON")?;
+
+    let measured_i = measurement.input_i.parse::<f64>().with_context(|| "invalid input_i in loudnorm JSON")?;
+    let measured_lra = measurement.input_lra.parse::<f64>().with_context(|| "invalid input_lra in loudnorm JSON")?;
+    let measured_tp = measurement.input_tp.parse::<f64>().with_context(|| "invalid input_tp in loudnorm JSON")?;
+    let measured_thresh = measurement.input_thresh.parse::<f64>().with_context(|| "invalid input_thresh in loudnorm JSON")?;
+    let target_offset = measurement.target_offset.parse::<f64>().with_context(|| "invalid target_offset in loudnorm JSON")?;
+    let dynamic_fallback = measurement.normalization_type.as_deref().unwrap_or("").eq_ignore_ascii_case("dynamic");
+
+    Ok(Some(LoudnessParams { measured_i, measured_lra, measured_tp, measured_thresh, target_offset, dynamic_fallback, target }))
+}
+
+// The JSON block `loudnorm=...:print_format=json` writes to stderr on the
+// measurement pass. ffmpeg reports every number as a string, so these stay
+// `String` and get parsed explicitly rather than coerced by serde.
+#[derive(Deserialize)]
+struct LoudnormMeasurement {
+    input_i: String,
+    input_tp: String,
+    input_lra: String,
+    input_thresh: String,
+    target_offset: String,
+    normalization_type: Option<String>,
 }